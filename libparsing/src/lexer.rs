@@ -1,6 +1,5 @@
 use std::cmp::PartialEq;
 use std::collections::HashMap;
-use std::panic::panic_any;
 
 #[derive(Debug, Clone)]
 pub struct Lexeme<'l, Token> {
@@ -48,6 +47,94 @@ fn char_to_lexing_state(
     LexingState::Symbol
 }
 
+fn continues_number(text: &str, c: char, next: Option<char>) -> bool {
+    if c.is_ascii_alphanumeric() || c == '_' {
+        return true;
+    }
+    let radix = radix_prefix(text).is_some();
+    let next_is_digit = next.is_some_and(|next| next.is_ascii_digit());
+    match c {
+        '.' => !radix && !text.contains(['.', 'e', 'E']) && next_is_digit,
+        '+' | '-' => !radix && text.ends_with(['e', 'E']) && next_is_digit,
+        _ => false,
+    }
+}
+
+fn radix_prefix(text: &str) -> Option<u32> {
+    match text.get(..2)?.to_ascii_lowercase().as_str() {
+        "0x" => Some(16),
+        "0o" => Some(8),
+        "0b" => Some(2),
+        _ => None,
+    }
+}
+
+fn digit_count(text: &str, radix: u32) -> usize {
+    if !text.starts_with(|c: char| c.is_digit(radix)) {
+        return 0;
+    }
+    text.find(|c: char| !c.is_digit(radix) && c != '_')
+        .unwrap_or(text.len())
+}
+
+fn is_number(text: &str) -> bool {
+    let (radix, mut rest) = match radix_prefix(text) {
+        Some(radix) => (radix, &text[2..]),
+        None => (10, text),
+    };
+    let count = digit_count(rest, radix);
+    if count == 0 {
+        return false;
+    }
+    rest = &rest[count..];
+    if radix == 10 {
+        if let Some(fraction) = rest.strip_prefix('.') {
+            let count = digit_count(fraction, 10);
+            if count == 0 {
+                return false;
+            }
+            rest = &fraction[count..];
+        }
+        if let Some(exponent) = rest.strip_prefix(['e', 'E']) {
+            let exponent = exponent.strip_prefix(['+', '-']).unwrap_or(exponent);
+            let count = digit_count(exponent, 10);
+            if count == 0 {
+                return false;
+            }
+            rest = &exponent[count..];
+        }
+    }
+    rest.is_empty()
+        || rest.starts_with(|c: char| c.is_ascii_alphabetic())
+            && rest.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+fn escape_char(c: char) -> Option<char> {
+    match c {
+        'n' => Some('\n'),
+        't' => Some('\t'),
+        'r' => Some('\r'),
+        '0' => Some('\0'),
+        '\\' => Some('\\'),
+        '"' => Some('"'),
+        _ => None,
+    }
+}
+
+pub fn unescape(string: &str) -> Option<String> {
+    let inner = string.strip_prefix('"')?.strip_suffix('"')?;
+    let mut result = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            result.push(escape_char(chars.next()?)?);
+        } else {
+            result.push(c);
+        }
+    }
+    Some(result)
+}
+
 pub fn lex<'l, Token: Copy>(
     source: &'l str,
     symbols: HashMap<&'static str, Token>,
@@ -67,8 +154,19 @@ pub fn lex<'l, Token: Copy>(
     let mut index_from = 0;
     let mut line_from = 0;
     let mut column_from = 0;
-    for (i, char) in source.chars().enumerate() {
-        let new_state = char_to_lexing_state(char, line_comment, block_comment);
+    let mut escaped = false;
+    let mut invalid_escapes = vec![];
+    // the trailing newline flushes whatever is still open at the end of the input
+    for (i, char) in source.char_indices().chain([(source.len(), '\n')]) {
+        let end = i + char.len_utf8();
+        let next = source.get(end..).and_then(|rest| rest.chars().next());
+        let mut new_state = char_to_lexing_state(char, line_comment, block_comment);
+        if state == LexingState::Number && continues_number(&source[index_from..i], char, next) {
+            new_state = LexingState::Number;
+        }
+        if matches!(state, LexingState::Ident { .. }) && (char.is_alphanumeric() || char == '_') {
+            new_state = state;
+        }
         if new_state != state {
             let mut ignore = false;
             match state {
@@ -83,13 +181,21 @@ pub fn lex<'l, Token: Copy>(
                     source: &source[index_from..i],
                 }),
                 LexingState::Number => lexemes.push(Lexeme {
-                    token: number,
+                    token: if is_number(&source[index_from..i]) {
+                        number
+                    } else {
+                        error
+                    },
                     line: (line_from, line),
                     column: (column_from, column),
                     source: &source[index_from..i],
                 }),
                 LexingState::Ident { upper } => lexemes.push(Lexeme {
-                    token: if upper { uppercase } else { lowercase },
+                    token: match keywords.get(&source[index_from..i]) {
+                        Some(keyword) => *keyword,
+                        None if upper => uppercase,
+                        None => lowercase,
+                    },
                     line: (line_from, line),
                     column: (column_from, column),
                     source: &source[index_from..i],
@@ -110,49 +216,63 @@ pub fn lex<'l, Token: Copy>(
         match state {
             LexingState::None => {}
             LexingState::String => {
-                if char == '"' && index_from < i {
+                if char == '\n' {
+                    escaped = false;
                     lexemes.push(Lexeme {
-                        token: string,
+                        token: error,
                         line: (line_from, line),
-                        column: (column_from, column),
-                        source: &source[index_from..=i],
+                        column: (column_from, column - 1),
+                        source: &source[index_from..i],
                     });
+                    lexemes.append(&mut invalid_escapes);
                     state = LexingState::None;
-                }
-            }
-            LexingState::Symbol => {
-                let token = symbols.get(&source[index_from..=i]);
-                if let Some(token) = token {
+                } else if escaped {
+                    escaped = false;
+                    if escape_char(char).is_none() {
+                        invalid_escapes.push(Lexeme {
+                            token: error,
+                            line: (line, line),
+                            column: (column - 2, column),
+                            source: &source[i - 1..end],
+                        });
+                    }
+                } else if char == '\\' {
+                    escaped = true;
+                } else if char == '"' && index_from < i {
                     lexemes.push(Lexeme {
-                        token: *token,
+                        token: string,
                         line: (line_from, line),
                         column: (column_from, column),
-                        source: &source[index_from..=i],
+                        source: &source[index_from..end],
                     });
+                    lexemes.append(&mut invalid_escapes);
                     state = LexingState::None;
                 }
             }
-            LexingState::Number => {}
-            LexingState::Ident { .. } => {
-                let token = keywords.get(&source[index_from..=i]);
-                if let Some(token) = token {
+            LexingState::Symbol => {
+                let text = &source[index_from..end];
+                let longer = next.is_some_and(|next| {
+                    let extended = format!("{}{}", text, next);
+                    symbols.keys().any(|symbol| symbol.starts_with(&extended))
+                });
+                let token = symbols.get(text);
+                if let Some(token) = token
+                    && !longer
+                {
                     lexemes.push(Lexeme {
                         token: *token,
                         line: (line_from, line),
                         column: (column_from, column),
-                        source: &source[index_from..=i],
+                        source: &source[index_from..end],
                     });
                     state = LexingState::None;
                 }
             }
+            LexingState::Number => {}
+            LexingState::Ident { .. } => {}
             LexingState::Comment { block } => {
                 if block {
-                    let Some((_, end)) = block_comment else {
-                        panic_any(
-                            "created a block comment without having a start character defined",
-                        )
-                    };
-                    if char == end {
+                    if block_comment.is_some_and(|(_, end)| char == end) {
                         state = LexingState::None;
                     }
                 } else {
@@ -169,3 +289,126 @@ pub fn lex<'l, Token: Copy>(
     }
     lexemes
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lexemes(source: &str) -> Vec<Lexeme<'_, &'static str>> {
+        let symbols = HashMap::from([(".", "."), ("=", "="), ("|", "|"), ("||", "||")]);
+        let keywords = HashMap::from([("let", "let"), ("use", "use"), ("true", "true")]);
+        lex(
+            source, symbols, keywords, "upper", "lower", "string", "number", "error", '/', None,
+        )
+    }
+
+    fn tokens(source: &str) -> Vec<(&'static str, &str)> {
+        lexemes(source)
+            .into_iter()
+            .map(|it| (it.token, it.source))
+            .collect()
+    }
+
+    #[test]
+    fn lex_number_forms() {
+        for number in ["1e9", "1.5e+3", "0xFF", "0b101", "1u8", "1_000"] {
+            assert_eq!(tokens(number), [("number", number)]);
+        }
+    }
+
+    #[test]
+    fn lex_invalid_numbers_as_errors() {
+        for number in ["0x", "1e", "0b102"] {
+            assert_eq!(tokens(number), [("error", number)]);
+        }
+    }
+
+    #[test]
+    fn lex_periods_after_numbers() {
+        assert_eq!(
+            tokens("1..10"),
+            [("number", "1"), (".", "."), (".", "."), ("number", "10")]
+        );
+        assert_eq!(tokens("v.0"), [("lower", "v"), (".", "."), ("number", "0")]);
+    }
+
+    #[test]
+    fn unescape_decodes_escapes() {
+        assert_eq!(
+            unescape(r#""a\n\t\r\0\\\"b""#),
+            Some("a\n\t\r\0\\\"b".to_string())
+        );
+        assert_eq!(unescape(r#""\q""#), None);
+        assert_eq!(unescape(r#""a\""#), None);
+    }
+
+    #[test]
+    fn lex_invalid_escape_after_its_string() {
+        let lexed = lexemes(r#"x = "a\qb" y"#);
+        let lexed = lexed
+            .iter()
+            .map(|it| (it.token, it.source, it.column))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            lexed,
+            [
+                ("lower", "x", (0, 1)),
+                ("=", "=", (2, 3)),
+                ("string", r#""a\qb""#, (4, 10)),
+                ("error", r"\q", (6, 8)),
+                ("lower", "y", (11, 12)),
+            ]
+        );
+    }
+
+    #[test]
+    fn lex_unterminated_strings() {
+        assert_eq!(tokens("\"abc\nlet"), [("error", "\"abc"), ("let", "let")]);
+        assert_eq!(tokens("\"abc"), [("error", "\"abc")]);
+        assert_eq!(tokens("\"abc\\"), [("error", "\"abc\\")]);
+        assert_eq!(
+            tokens("\"abc\\\nlet"),
+            [("error", "\"abc\\"), ("let", "let")]
+        );
+    }
+
+    #[test]
+    fn lex_multi_byte_input() {
+        assert_eq!(
+            tokens("\"ü\" é 😀"),
+            [("string", "\"ü\""), ("lower", "é"), ("error", "😀")]
+        );
+        let lexed = lexemes("\"\\ü\"");
+        let lexed = lexed
+            .iter()
+            .map(|it| (it.token, it.source, it.column))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            lexed,
+            [("string", "\"\\ü\"", (0, 4)), ("error", "\\ü", (1, 3))]
+        );
+    }
+
+    #[test]
+    fn lex_longest_symbol() {
+        assert_eq!(
+            tokens("a || b"),
+            [("lower", "a"), ("||", "||"), ("lower", "b")]
+        );
+        assert_eq!(tokens("| |"), [("|", "|"), ("|", "|")]);
+    }
+
+    #[test]
+    fn lex_keywords_as_whole_identifiers() {
+        assert_eq!(
+            tokens("letter user trueish use true"),
+            [
+                ("lower", "letter"),
+                ("lower", "user"),
+                ("lower", "trueish"),
+                ("use", "use"),
+                ("true", "true"),
+            ]
+        );
+    }
+}
@@ -10,32 +10,104 @@ pub type ParseResult<'l, Token, T> = Result<T, Vec<ParseError<'l, Token>>>;
 pub struct ParseError<'l, Token: ParseErrorToken> {
     expected: Vec<Token>,
     got: Option<Lexeme<'l, Token>>,
+    end: Option<(usize, usize)>,
+    message: Option<String>,
 }
 
 impl<'l, Token: ParseErrorToken> ParseError<'l, Token> {
-    pub fn none<T>(expected: Vec<Token>) -> Result<T, Vec<Self>> {
+    pub fn none<T>(
+        expected: Vec<Token>,
+        after: Option<&Lexeme<'l, Token>>,
+    ) -> Result<T, Vec<Self>> {
         Err(vec![ParseError {
             expected,
             got: None,
+            end: after.map(|last| (last.line.1, last.column.1)),
+            message: None,
         }])
     }
-    pub fn fancy_print(&self, file_name: String) -> String {
-        let message = match self.got {
-            None => format!("=> {}\n\tunexpected end of input", file_name),
-            Some(ref got) => format!(
-                "=> {}:{}:{}\n\tunexpected `{}`",
-                file_name, got.line.0, got.column.0, got.source
-            ),
+    pub fn merge(errors: Vec<Self>) -> Vec<Self> {
+        let mut merged: Vec<Self> = vec![];
+        for mut error in errors {
+            let position = error.position();
+            match merged
+                .iter_mut()
+                .find(|it| it.position() == position && it.message == error.message)
+            {
+                Some(existing) => existing.expected.append(&mut error.expected),
+                None => merged.push(error),
+            }
+        }
+        for error in &mut merged {
+            error.expected.sort_by_key(|it| it.as_text());
+            error.expected.dedup_by_key(|it| it.as_text());
+        }
+        merged
+    }
+    pub fn got(&self) -> Option<&Lexeme<'l, Token>> {
+        self.got.as_ref()
+    }
+    fn position(&self) -> Option<(usize, usize)> {
+        match self.got {
+            None => self.end,
+            Some(ref got) => Some((got.line.0, got.column.0)),
+        }
+    }
+    pub fn diagnostic(&self, file_name: String) -> Diagnostic {
+        let message = match (&self.message, &self.got) {
+            (Some(message), _) => message.clone(),
+            (None, None) => "unexpected end of input".to_string(),
+            (None, Some(got)) => format!("unexpected `{}`", got.source),
         };
-        format!(
-            "{},\n\texpected {}",
+        Diagnostic {
+            file_name,
+            position: self.position(),
+            message,
+            expected: self.expected.iter().map(|it| it.as_text()).collect(),
+        }
+    }
+    pub fn fancy_print(&self, file_name: String) -> String {
+        self.diagnostic(file_name).fancy_print()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    pub file_name: String,
+    pub position: Option<(usize, usize)>,
+    pub message: String,
+    pub expected: Vec<&'static str>,
+}
+
+impl Diagnostic {
+    pub fn new(file_name: String, position: Option<(usize, usize)>, message: String) -> Self {
+        Self {
+            file_name,
+            position,
             message,
-            self.expected
-                .iter()
-                .map(|it| it.as_text())
-                .collect::<Vec<&str>>()
-                .join(", ")
-        )
+            expected: vec![],
+        }
+    }
+    pub fn fancy_print(&self) -> String {
+        self.print(",\n\texpected ")
+    }
+    pub fn short_print(&self) -> String {
+        self.print(", expected ")
+    }
+    fn print(&self, expected_separator: &str) -> String {
+        let location = match self.position {
+            None => self.file_name.clone(),
+            Some((line, column)) => format!("{}:{}:{}", self.file_name, line + 1, column + 1),
+        };
+        let mut message = format!("{}: error: {}", location, self.message);
+        if !self.expected.is_empty() {
+            message.push_str(expected_separator);
+            if self.expected.len() > 1 {
+                message.push_str("one of ");
+            }
+            message.push_str(&self.expected.join(", "));
+        }
+        message
     }
 }
 
@@ -44,6 +116,16 @@ impl<'l, Token: ParseErrorToken> Lexeme<'l, Token> {
         Err(vec![ParseError {
             expected,
             got: Some(self),
+            end: None,
+            message: None,
+        }])
+    }
+    pub fn error_message<T>(self, message: String) -> Result<T, Vec<ParseError<'l, Token>>> {
+        Err(vec![ParseError {
+            expected: vec![],
+            got: Some(self),
+            end: None,
+            message: Some(message),
         }])
     }
 }
@@ -1,5 +1,5 @@
 use crate::lexer::Lexeme;
-use crate::parse_error::{ParseErrorToken, ParseResult};
+use crate::parse_error::{ParseError, ParseErrorToken, ParseResult};
 use crate::walker::Walker;
 
 pub trait Parser<'l, Token: 'l + ParseErrorToken, T>:
@@ -23,40 +23,198 @@ pub fn parse<'l, Token: 'l + PartialEq + ParseErrorToken, T>(
     parser(tokens)
 }
 
-pub fn split<'l, Token: 'l + PartialEq + ParseErrorToken, A, B>(
+pub fn expect<'l, Token: 'l + Clone + PartialEq + ParseErrorToken>(
+    walker: &mut Walker<'l, Lexeme<'l, Token>>,
+    expected: &[Token],
+) -> ParseResult<'l, Token, Lexeme<'l, Token>> {
+    let Some(next) = walker.next() else {
+        return ParseError::none(expected.to_vec(), walker.last());
+    };
+    if !expected.contains(&next.token) {
+        return next.clone().error(expected.to_vec());
+    }
+    Ok(next.clone())
+}
+
+pub fn expect_end<'l, Token: 'l + Clone + ParseErrorToken>(
+    walker: &mut Walker<'l, Lexeme<'l, Token>>,
+    expected: &[Token],
+) -> ParseResult<'l, Token, ()> {
+    match walker.next() {
+        None => Ok(()),
+        Some(next) => next.clone().error(expected.to_vec()),
+    }
+}
+
+pub type Chunk<'l, Token, A> = (
+    Lexeme<'l, Token>,
+    Lexeme<'l, Token>,
+    ParseResult<'l, Token, A>,
+);
+
+pub fn split<'l, Token: 'l + Clone + PartialEq + ParseErrorToken, A, B>(
     on: &[Token],
+    brackets: &[(Token, Token)],
     then: impl Parser<'l, Token, A>,
-    combine: impl (Fn(Vec<A>) -> B) + Clone,
+    combine: impl (Fn(Vec<Chunk<'l, Token, A>>) -> B) + Clone,
 ) -> impl Parser<'l, Token, B> {
     move |mut walker| {
         let mut split = vec![];
+        let mut open = vec![];
+        if let Some(first) = walker.current() {
+            track_brackets(brackets, first, &mut open);
+        }
         loop {
             walker.next();
             let Some(current) = walker.current() else {
                 break;
             };
-            if on.contains(&current.token) {
+            let top_level = open.is_empty();
+            track_brackets(brackets, current, &mut open);
+            if top_level && on.contains(&current.token) {
                 split.push(walker.drop_tail());
             }
         }
         walker.reset();
         split.push(walker);
-        let parsed = split
-            .into_iter()
-            .map(then.clone())
-            .collect::<Vec<ParseResult<'l, Token, A>>>();
-        if parsed.iter().any(|it| it.is_err()) {
-            return Err(parsed
-                .into_iter()
-                .filter_map(|it| it.err())
-                .flatten()
-                .collect());
+        // everything after an unclosed bracket ends up in the last chunk
+        let mut unclosed = open.pop().map(|bracket| {
+            let message = format!("unclosed `{}`", bracket.source);
+            bracket.error_message::<()>(message).unwrap_err()
+        });
+        let last_chunk = split.len() - 1;
+        let mut chunks = vec![];
+        for (index, chunk) in split.into_iter().enumerate() {
+            let (Some(first), Some(last)) = (chunk.current(), chunk.last()) else {
+                continue;
+            };
+            let (first, last) = (first.clone(), last.clone());
+            let parsed = if index == last_chunk
+                && let Some(errors) = unclosed.take()
+            {
+                Err(errors)
+            } else {
+                then(chunk)
+            };
+            chunks.push((first, last, parsed));
+        }
+        Ok(combine(chunks))
+    }
+}
+
+fn track_brackets<'l, Token: Clone + PartialEq>(
+    brackets: &[(Token, Token)],
+    lexeme: &Lexeme<'l, Token>,
+    open: &mut Vec<Lexeme<'l, Token>>,
+) {
+    if brackets.iter().any(|(opening, _)| *opening == lexeme.token) {
+        open.push(lexeme.clone());
+    } else if brackets.iter().any(|(_, closing)| *closing == lexeme.token) {
+        open.pop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lexer::lex;
+    use std::collections::HashMap;
+
+    impl ParseErrorToken for &'static str {
+        fn as_text(&self) -> &'static str {
+            self
         }
-        Ok(combine(
-            parsed
-                .into_iter()
-                .filter_map(|it| it.ok())
-                .collect::<Vec<A>>(),
-        ))
+    }
+
+    const BRACKETS: [(&str, &str); 2] = [("(", ")"), ("[", "]")];
+
+    fn chunks(source: &str) -> Vec<Result<String, Vec<String>>> {
+        let symbols = HashMap::from([("(", "("), (")", ")"), ("[", "["), ("]", "]")]);
+        let keywords = HashMap::from([("kw", "kw")]);
+        let tokens = lex(
+            source, symbols, keywords, "upper", "lower", "string", "number", "error", '/', None,
+        );
+        let parsed = parse(
+            &tokens,
+            split(
+                &["kw"],
+                &BRACKETS,
+                |mut walker| {
+                    let mut chunk = vec![];
+                    while let Some(current) = walker.current() {
+                        chunk.push(current.source);
+                        walker.next();
+                    }
+                    Ok(chunk.join(" "))
+                },
+                |chunks| chunks,
+            ),
+        );
+        parsed
+            .expect("split does not fail as a whole")
+            .into_iter()
+            .map(|(_, _, parsed)| {
+                parsed.map_err(|errors| {
+                    errors
+                        .iter()
+                        .map(|error| error.diagnostic("test".to_string()).short_print())
+                        .collect()
+                })
+            })
+            .collect()
+    }
+
+    #[test]
+    fn split_on_top_level_keywords() {
+        assert_eq!(chunks("kw a kw b"), [Ok("kw a".into()), Ok("kw b".into())]);
+    }
+
+    #[test]
+    fn split_skips_empty_input() {
+        assert_eq!(chunks(""), []);
+    }
+
+    #[test]
+    fn split_ignores_keywords_in_brackets() {
+        assert_eq!(
+            chunks("kw a (kw b) kw c [kw d]"),
+            [Ok("kw a ( kw b )".into()), Ok("kw c [ kw d ]".into())]
+        );
+    }
+
+    #[test]
+    fn split_ignores_keywords_in_nested_brackets() {
+        assert_eq!(
+            chunks("kw ([kw] (kw)) kw"),
+            [Ok("kw ( [ kw ] ( kw ) )".into()), Ok("kw".into())]
+        );
+    }
+
+    #[test]
+    fn split_reports_innermost_unclosed_bracket() {
+        assert_eq!(
+            chunks("kw a ([b] (c\nkw d"),
+            [Err(vec!["test:1:11: error: unclosed `(`".into()])]
+        );
+    }
+
+    #[test]
+    fn split_keeps_chunks_before_unclosed_bracket() {
+        assert_eq!(
+            chunks("kw a kw b [c] kw d (e\nkw f"),
+            [
+                Ok("kw a".into()),
+                Ok("kw b [ c ]".into()),
+                Err(vec!["test:1:20: error: unclosed `(`".into()]),
+            ]
+        );
+    }
+
+    #[test]
+    fn split_ignores_unmatched_closing_bracket() {
+        assert_eq!(
+            chunks("kw a) kw b"),
+            [Ok("kw a )".into()), Ok("kw b".into())]
+        );
     }
 }
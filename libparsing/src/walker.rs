@@ -18,6 +18,15 @@ impl<'l, T> Walker<'l, T> {
         }
         self.items.get(self.pos)
     }
+    pub fn last(&self) -> Option<&T> {
+        self.items[..self.len].last()
+    }
+    pub fn peek(&self) -> Option<&T> {
+        if self.pos + 1 >= self.len {
+            return None;
+        }
+        self.items.get(self.pos + 1)
+    }
     pub fn next(&mut self) -> Option<&T> {
         if self.pos < self.len {
             self.pos += 1;
@@ -31,6 +40,7 @@ impl<'l, T> Walker<'l, T> {
             pos: 0,
         };
         self.items = &self.items[self.pos..];
+        self.len -= self.pos;
         self.pos = 0;
         tail
     }
@@ -3,10 +3,27 @@ pub enum Ast {
     Use {
         from: String,
         name: Option<String>,
-        items: Vec<String>
+        items: Vec<Import>,
+        line: (usize, usize),
+        column: (usize, usize),
     },
-    Doc(String),
-    Typ, // todo
-    Def, // todo
-    Let, // todo
-}
\ No newline at end of file
+    Typ {
+        doc: Vec<String>,
+    }, // todo
+    Def {
+        doc: Vec<String>,
+    }, // todo
+    Let {
+        doc: Vec<String>,
+    }, // todo
+    Error {
+        line: (usize, usize),
+        column: (usize, usize),
+    },
+}
+#[derive(Debug)]
+pub enum Import {
+    Name(String),
+    Alias { name: String, alias: String },
+    Glob,
+}
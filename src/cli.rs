@@ -0,0 +1,127 @@
+use crate::report::{self, ErrorFormat};
+use std::env;
+use std::path::PathBuf;
+
+pub const USAGE: &str = "usage:
+    soup check [--emit=tokens,ast] [--timings] [--max-errors=N] \
+[--error-format=pretty|short] [file|directory|pattern]...
+    soup init <name>
+    soup tokens <file>
+    soup grammar
+
+environment (overridden by flags):
+    SOUP_MAX_ERRORS  default for --max-errors
+    SOUP_PATH        extra directories to search for imported modules";
+
+pub enum Command {
+    Check {
+        inputs: Vec<String>,
+        emit: Vec<Emit>,
+        timings: bool,
+        report: report::Config,
+        search_paths: Vec<PathBuf>,
+    },
+    Init {
+        name: String,
+    },
+    Tokens {
+        input: String,
+    },
+    Grammar,
+}
+
+#[derive(PartialEq)]
+pub enum Emit {
+    Tokens,
+    Ast,
+}
+
+pub fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Command, String> {
+    let Some(command) = args.next() else {
+        return Err("missing command".to_string());
+    };
+    match command.as_str() {
+        "check" => {
+            let mut inputs = vec![];
+            let mut emit = vec![];
+            let mut timings = false;
+            let mut report = report::Config::default();
+            let mut max_errors_flag = false;
+            let search_paths = env::var_os("SOUP_PATH")
+                .map(|paths| env::split_paths(&paths).collect())
+                .unwrap_or_default();
+            for arg in args {
+                if let Some(kinds) = arg.strip_prefix("--emit=") {
+                    for kind in kinds.split(',') {
+                        emit.push(parse_emit(kind)?);
+                    }
+                } else if let Some(max_errors) = arg.strip_prefix("--max-errors=") {
+                    report.max_errors = max_errors
+                        .parse()
+                        .map_err(|_| format!("invalid error count `{}`", max_errors))?;
+                    max_errors_flag = true;
+                } else if let Some(format) = arg.strip_prefix("--error-format=") {
+                    report.format = match format {
+                        "pretty" => ErrorFormat::Pretty,
+                        "short" => ErrorFormat::Short,
+                        _ => return Err(format!("unknown error format `{}`", format)),
+                    };
+                } else if arg == "--timings" {
+                    timings = true;
+                } else if arg.starts_with("--") {
+                    return Err(format!("unknown flag `{}`", arg));
+                } else {
+                    inputs.push(arg);
+                }
+            }
+            if !max_errors_flag && let Ok(max_errors) = env::var("SOUP_MAX_ERRORS") {
+                report.max_errors = max_errors
+                    .parse()
+                    .map_err(|_| format!("invalid SOUP_MAX_ERRORS `{}`", max_errors))?;
+            }
+            if inputs.is_empty() {
+                inputs.push("main.soup".to_string());
+            }
+            Ok(Command::Check {
+                inputs,
+                emit,
+                timings,
+                report,
+                search_paths,
+            })
+        }
+        "init" => {
+            let Some(name) = args.next() else {
+                return Err("missing project name".to_string());
+            };
+            if let Some(extra) = args.next() {
+                return Err(format!("unexpected argument `{}`", extra));
+            }
+            Ok(Command::Init { name })
+        }
+        "tokens" => {
+            let Some(input) = args.next() else {
+                return Err("missing file".to_string());
+            };
+            if let Some(extra) = args.next() {
+                return Err(format!("unexpected argument `{}`", extra));
+            }
+            Ok(Command::Tokens { input })
+        }
+        "grammar" => {
+            if let Some(extra) = args.next() {
+                return Err(format!("unexpected argument `{}`", extra));
+            }
+            Ok(Command::Grammar)
+        }
+        _ => Err(format!("unknown command `{}`", command)),
+    }
+}
+
+fn parse_emit(kind: &str) -> Result<Emit, String> {
+    match kind {
+        "tokens" => Ok(Emit::Tokens),
+        "ast" => Ok(Emit::Ast),
+        _ => Err(format!("unknown emit kind `{}`", kind)),
+    }
+}
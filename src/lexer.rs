@@ -2,37 +2,56 @@ use crate::token::Token;
 use libparsing::lexer;
 use libparsing::lexer::Lexeme;
 use crate::map;
+use std::collections::HashMap;
+
+pub const LINE_COMMENT: char = '/';
+pub const BLOCK_COMMENT: Option<(char, char)> = Some(('<', '>'));
+
+pub fn symbols() -> HashMap<&'static str, Token> {
+    map! {
+        "=" => Token::Equals,
+        "|" => Token::Pipe,
+        ";" => Token::Semicolon,
+        ":" => Token::Colon,
+        "," => Token::Comma,
+        "." => Token::Period,
+        "#" => Token::Hashtag,
+        "*" => Token::Asterisk,
+        "&&" => Token::DoubleAmpersand,
+        "||" => Token::DoublePipe,
+        "!" => Token::ExclamationMark,
+        "[" => Token::SquareOpen,
+        "]" => Token::SquareClose,
+        "(" => Token::RoundOpen,
+        ")" => Token::RoundClose,
+    }
+}
+
+pub fn keywords() -> HashMap<&'static str, Token> {
+    map! {
+        "def" => Token::KwDef,
+        "let" => Token::KwLet,
+        "typ" => Token::KwTyp,
+        "pub" => Token::KwPub,
+        "use" => Token::KwUse,
+        "doc" => Token::KwDoc,
+        "as" => Token::KwAs,
+        "true" => Token::KwTrue,
+        "false" => Token::KwFalse,
+    }
+}
 
 pub fn lex(input: &str) -> Vec<Lexeme<'_, Token>> {
     lexer::lex(
         input,
-        map! {
-            "=" => Token::Equals,
-            "|" => Token::Pipe,
-            ";" => Token::Semicolon,
-            ":" => Token::Colon,
-            "," => Token::Comma,
-            "." => Token::Period,
-            "#" => Token::Hashtag,
-            "[" => Token::SquareOpen,
-            "]" => Token::SquareClose,
-            "(" => Token::RoundOpen,
-            ")" => Token::RoundClose,
-        },
-        map! {
-            "def" => Token::KwDef,
-            "let" => Token::KwLet,
-            "typ" => Token::KwTyp,
-            "pub" => Token::KwPub,
-            "use" => Token::KwUse,
-            "doc" => Token::KwDoc,
-        },
+        symbols(),
+        keywords(),
         Token::TypeName,
         Token::ValueName,
         Token::String,
         Token::Number,
         Token::LexError,
-        '/',
-        Some(('<', '>')),
+        LINE_COMMENT,
+        BLOCK_COMMENT,
     )
 }
@@ -0,0 +1,188 @@
+use crate::ast::Ast;
+use crate::lexer::lex;
+use crate::parser::parse;
+use crate::source::SourceProvider;
+use libparsing::parse_error::{Diagnostic, ParseError};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Component, Path, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant};
+
+#[derive(Debug)]
+pub struct Module {
+    pub ast: Vec<Ast>,
+    pub errors: Vec<Diagnostic>,
+    pub timings: Timings,
+}
+
+#[derive(Debug, Default)]
+pub struct Timings {
+    pub lex: Duration,
+    pub parse: Duration,
+    pub tokens: usize,
+}
+
+pub type Modules = BTreeMap<String, Module>;
+
+pub fn load(
+    sources: &(impl SourceProvider + Sync),
+    entries: &[String],
+    search_paths: &[PathBuf],
+) -> (Modules, Vec<Diagnostic>) {
+    let threads = thread::available_parallelism().map_or(1, |it| it.get());
+    let mut modules = Modules::new();
+    let mut unreadable = vec![];
+    let mut pending = entries
+        .iter()
+        .map(|entry| normalize(Path::new(entry)))
+        .collect::<Vec<String>>();
+    while !pending.is_empty() {
+        let mut queued = BTreeSet::new();
+        pending.retain(|path| !modules.contains_key(path) && queued.insert(path.clone()));
+        let mut imported = vec![];
+        for chunk in pending.chunks(threads) {
+            let loaded = thread::scope(|scope| {
+                chunk
+                    .iter()
+                    .map(|path| scope.spawn(move || load_module(sources, path, search_paths)))
+                    .collect::<Vec<_>>()
+                    .into_iter()
+                    .map(|handle| handle.join().expect("Failed to load module"))
+                    .collect::<Vec<Option<(Module, Vec<String>)>>>()
+            });
+            for (path, loaded) in chunk.iter().zip(loaded) {
+                let Some((module, mut imports)) = loaded else {
+                    unreadable.push(Diagnostic::new(
+                        path.clone(),
+                        None,
+                        "cannot read file".to_string(),
+                    ));
+                    continue;
+                };
+                imported.append(&mut imports);
+                modules.insert(path.clone(), module);
+            }
+        }
+        pending = imported;
+    }
+    (modules, unreadable)
+}
+
+fn load_module(
+    sources: &impl SourceProvider,
+    path: &str,
+    search_paths: &[PathBuf],
+) -> Option<(Module, Vec<String>)> {
+    let input = sources.read(path)?;
+    let start = Instant::now();
+    let tokens = lex(&input);
+    let lexed = Instant::now();
+    let (ast, errors) = parse(&tokens);
+    let timings = Timings {
+        lex: lexed - start,
+        parse: lexed.elapsed(),
+        tokens: tokens.len(),
+    };
+    let mut errors = ParseError::merge(errors)
+        .iter()
+        .map(|error| error.diagnostic(path.to_string()))
+        .collect::<Vec<Diagnostic>>();
+    let imports = imports(sources, path, &ast, search_paths, &mut errors);
+    errors.sort_by_key(|error| error.position);
+    let module = Module {
+        ast,
+        errors,
+        timings,
+    };
+    Some((module, imports))
+}
+
+fn imports(
+    sources: &impl SourceProvider,
+    path: &str,
+    ast: &[Ast],
+    search_paths: &[PathBuf],
+    errors: &mut Vec<Diagnostic>,
+) -> Vec<String> {
+    let directory = Path::new(path).parent().unwrap_or(Path::new(""));
+    let mut imports = vec![];
+    for item in ast {
+        let Ast::Use {
+            from, line, column, ..
+        } = item
+        else {
+            continue;
+        };
+        let file = format!("{}.soup", from);
+        let found = [directory.to_path_buf()]
+            .iter()
+            .chain(search_paths)
+            .map(|root| normalize(&root.join(&file)))
+            .find(|candidate| sources.exists(candidate));
+        match found {
+            Some(found) => imports.push(found),
+            None => errors.push(Diagnostic::new(
+                path.to_string(),
+                Some((line.0, column.0)),
+                format!("cannot find module \"{}\"", from),
+            )),
+        }
+    }
+    imports
+}
+
+fn normalize(path: &Path) -> String {
+    let mut components = vec![];
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => match components.last() {
+                Some(Component::Normal(_)) => {
+                    components.pop();
+                }
+                Some(Component::RootDir | Component::Prefix(_)) => {}
+                _ => components.push(component),
+            },
+            _ => components.push(component),
+        }
+    }
+    components.iter().collect::<PathBuf>().display().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::source::MemorySource;
+    use std::collections::HashMap;
+
+    #[test]
+    fn load_follows_imports_from_memory() {
+        let mut sources = MemorySource::new(HashMap::from([(
+            "app/main.soup".to_string(),
+            "use \"lib\" [helper]\nuse \"./lib\"\n".to_string(),
+        )]));
+        sources.insert("app/lib.soup", "use \"io\"\nlet x\n".to_string());
+        let entries = ["./app/main.soup".to_string(), "app/gone.soup".to_string()];
+        let (modules, unreadable) = load(&sources, &entries, &[]);
+        assert_eq!(
+            modules.keys().collect::<Vec<&String>>(),
+            ["app/lib.soup", "app/main.soup"]
+        );
+        assert_eq!(unreadable.len(), 1);
+        assert_eq!(
+            unreadable[0].short_print(),
+            "app/gone.soup: error: cannot read file"
+        );
+        assert!(matches!(
+            modules["app/main.soup"].ast.as_slice(),
+            [Ast::Use { from, .. }, Ast::Use { .. }] if from == "lib"
+        ));
+        assert!(modules["app/main.soup"].errors.is_empty());
+        let errors = &modules["app/lib.soup"].errors;
+        assert_eq!(errors.len(), 1);
+        assert_eq!(
+            errors[0].short_print(),
+            "app/lib.soup:1:5: error: cannot find module \"io\""
+        );
+    }
+}
@@ -1,16 +1,199 @@
 pub mod ast;
+pub mod cli;
 pub mod lexer;
+pub mod loader;
 pub mod parser;
+pub mod report;
+pub mod source;
 pub mod token;
 pub mod macros;
 
-use crate::lexer::lex;
+use crate::cli::{Command, Emit, USAGE, parse_args};
+use crate::lexer::{BLOCK_COMMENT, LINE_COMMENT, keywords, lex, symbols};
+use crate::loader::load;
+use crate::parser::{BRACKETS, TOP_LEVEL_KEYWORDS};
+use crate::report::{Config, show_errors};
+use crate::source::{DiskSource, SourceProvider, expand_inputs};
+use crate::token::Token;
+use libparsing::parse_error::ParseErrorToken;
+use std::collections::HashMap;
+use std::env;
 use std::fs;
-use crate::parser::parse;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
 
-fn main() {
-    let input = fs::read_to_string("main.soup").expect("Failed to read input file");
-    let tokens = lex(&input);
-    let ast = parse(&tokens);
-    println!("{:#?}", ast);
+const MAIN_TEMPLATE: &str = r#"doc "Entry point of the program"
+def main = String
+let main = "Hello, World!"
+"#;
+
+fn main() -> ExitCode {
+    let command = match parse_args(env::args().skip(1)) {
+        Ok(command) => command,
+        Err(message) => {
+            eprintln!("{}\n{}", message, USAGE);
+            return ExitCode::FAILURE;
+        }
+    };
+    match command {
+        Command::Check {
+            inputs,
+            emit,
+            timings,
+            report,
+            search_paths,
+        } => match expand_inputs(&inputs) {
+            Ok(inputs) => check(&inputs, &search_paths, &emit, timings, &report),
+            Err(message) => {
+                eprintln!("{}", message);
+                ExitCode::FAILURE
+            }
+        },
+        Command::Init { name } => init(&name),
+        Command::Tokens { input } => tokens(&input),
+        Command::Grammar => grammar(),
+    }
+}
+
+fn check(
+    inputs: &[String],
+    search_paths: &[PathBuf],
+    emit: &[Emit],
+    timings: bool,
+    report: &Config,
+) -> ExitCode {
+    let (modules, unreadable) = load(&DiskSource, inputs, search_paths);
+    for (path, module) in &modules {
+        if emit.contains(&Emit::Tokens)
+            && let Some(input) = DiskSource.read(path)
+        {
+            println!("=> {}\n{:#?}", path, lex(&input));
+        }
+        if emit.contains(&Emit::Ast) {
+            println!("=> {}\n{:#?}", path, module.ast);
+        }
+        if timings {
+            println!(
+                "=> {}\n\tlex: {:?} ({} tokens)\n\tparse: {:?} ({} declarations, {} errors)",
+                path,
+                module.timings.lex,
+                module.timings.tokens,
+                module.timings.parse,
+                module.ast.len(),
+                module.errors.len()
+            );
+        }
+    }
+    let errors = unreadable
+        .iter()
+        .chain(modules.values().flat_map(|module| &module.errors));
+    let summary = match show_errors(errors, report, &mut io::stderr()) {
+        Ok(summary) => summary,
+        Err(error) => {
+            // stderr itself may be what failed, so a second failure is ignored
+            let _ = writeln!(io::stderr(), "error: cannot write errors: {}", error);
+            return ExitCode::FAILURE;
+        }
+    };
+    if summary.errors > 0 {
+        return ExitCode::FAILURE;
+    }
+    ExitCode::SUCCESS
+}
+
+fn init(name: &str) -> ExitCode {
+    let directory = Path::new(name);
+    if directory.exists() {
+        eprintln!("{}: error: already exists", name);
+        return ExitCode::FAILURE;
+    }
+    let created = fs::create_dir_all(directory)
+        .and_then(|_| fs::write(directory.join("main.soup"), MAIN_TEMPLATE));
+    if let Err(error) = created {
+        eprintln!("{}: error: {}", name, error);
+        return ExitCode::FAILURE;
+    }
+    println!("created {}", directory.join("main.soup").display());
+    ExitCode::SUCCESS
+}
+
+fn tokens(input: &str) -> ExitCode {
+    let Some(source) = DiskSource.read(input) else {
+        eprintln!("{}: error: cannot read file", input);
+        return ExitCode::FAILURE;
+    };
+    let mut depth = 0usize;
+    let rows = lex(&source)
+        .into_iter()
+        .map(|lexeme| {
+            if BRACKETS.iter().any(|(_, close)| *close == lexeme.token) {
+                depth = depth.saturating_sub(1);
+            }
+            let row = [
+                format!("{}", lexeme.line.0 + 1),
+                format!("{}-{}", lexeme.column.0 + 1, lexeme.column.1),
+                format!("{:?}", lexeme.token),
+                format!("{}", depth),
+                format!("{:?}", lexeme.source),
+            ];
+            if BRACKETS.iter().any(|(open, _)| *open == lexeme.token) {
+                depth += 1;
+            }
+            row
+        })
+        .collect::<Vec<[String; 5]>>();
+    let header = ["line", "columns", "token", "depth", "source"].map(str::to_string);
+    let mut widths = header.clone().map(|it| it.len());
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.len());
+        }
+    }
+    for row in [header].iter().chain(&rows) {
+        println!(
+            "{:>w0$}  {:<w1$}  {:<w2$}  {:>w3$}  {}",
+            row[0],
+            row[1],
+            row[2],
+            row[3],
+            row[4],
+            w0 = widths[0],
+            w1 = widths[1],
+            w2 = widths[2],
+            w3 = widths[3],
+        );
+    }
+    ExitCode::SUCCESS
+}
+
+fn grammar() -> ExitCode {
+    let texts = |tokens: &[Token]| {
+        tokens
+            .iter()
+            .map(|token| token.as_text())
+            .collect::<Vec<&str>>()
+            .join(" ")
+    };
+    let sorted = |table: HashMap<&'static str, Token>| {
+        let mut tokens = table.into_values().collect::<Vec<Token>>();
+        tokens.sort_by_key(|token| token.as_text());
+        tokens
+    };
+    println!("declarations: {}", texts(&TOP_LEVEL_KEYWORDS));
+    println!("keywords: {}", texts(&sorted(keywords())));
+    println!("symbols: {}", texts(&sorted(symbols())));
+    println!(
+        "brackets: {}",
+        BRACKETS
+            .iter()
+            .map(|(open, close)| format!("{} {}", open.as_text(), close.as_text()))
+            .collect::<Vec<String>>()
+            .join(", ")
+    );
+    println!("line comment: `{}`", LINE_COMMENT);
+    if let Some((start, end)) = BLOCK_COMMENT {
+        println!("block comment: `{}` `{}`", start, end);
+    }
+    ExitCode::SUCCESS
 }
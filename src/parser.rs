@@ -1,56 +1,215 @@
-use crate::ast::Ast;
+use crate::ast::{Ast, Import};
 use crate::token::Token;
-use libparsing::lexer::Lexeme;
+use libparsing::lexer::{self, Lexeme};
 use libparsing::parse_error::{ParseError, ParseResult};
 use libparsing::parser;
 use libparsing::walker::Walker;
 
-pub fn parse<'l>(tokens: &'l[Lexeme<Token>]) -> ParseResult<'l, Token, Vec<Ast>> {
-    let top_level_keywords = vec![
-        Token::KwUse,
-        Token::KwDoc,
-        Token::KwTyp,
-        Token::KwDef,
-        Token::KwLet,
-    ];
-    parser::parse(
+pub const TOP_LEVEL_KEYWORDS: [Token; 5] = [
+    Token::KwUse,
+    Token::KwDoc,
+    Token::KwTyp,
+    Token::KwDef,
+    Token::KwLet,
+];
+
+pub const BRACKETS: [(Token, Token); 2] = [
+    (Token::SquareOpen, Token::SquareClose),
+    (Token::RoundOpen, Token::RoundClose),
+];
+
+pub fn parse<'l>(tokens: &'l [Lexeme<Token>]) -> (Vec<Ast>, Vec<ParseError<'l, Token>>) {
+    let top_level_keywords = TOP_LEVEL_KEYWORDS.to_vec();
+    let parsed = parser::parse(
         tokens,
         parser::split(
             &top_level_keywords,
+            &BRACKETS,
             |walker| {
                 let Some(current) = walker.current() else {
-                    return ParseError::none(top_level_keywords.clone());
+                    return ParseError::none(top_level_keywords.clone(), None);
                 };
                 match current.token {
-                    Token::KwUse => parse_use(walker),
-                    Token::KwDoc => parse_doc(walker),
-                    Token::KwTyp => parse_typ(walker),
-                    Token::KwDef => parse_def(walker),
-                    Token::KwLet => parse_let(walker),
+                    Token::KwUse => parse_use(walker).map(Declaration::Item),
+                    Token::KwDoc => parse_doc(walker).map(Declaration::Doc),
+                    Token::KwTyp => parse_typ(walker).map(Declaration::Item),
+                    Token::KwDef => parse_def(walker).map(Declaration::Item),
+                    Token::KwLet => parse_let(walker).map(Declaration::Item),
                     _ => current.clone().error(top_level_keywords.clone()),
                 }
             },
-            |all| all,
+            attach_docs,
         ),
-    )
+    );
+    let (ast, errors) = parsed.unwrap_or_else(|errors| {
+        let ast = match (tokens.first(), tokens.last()) {
+            (Some(first), Some(last)) => vec![Ast::Error {
+                line: (first.line.0, last.line.1),
+                column: (first.column.0, last.column.1),
+            }],
+            _ => vec![],
+        };
+        (ast, errors)
+    });
+    let lex_errors = tokens
+        .iter()
+        .filter(|it| it.token == Token::LexError)
+        .collect::<Vec<&Lexeme<Token>>>();
+    let mut errors = errors
+        .into_iter()
+        .filter(|error| {
+            !error
+                .got()
+                .is_some_and(|got| lex_errors.iter().any(|it| contains(got, it)))
+        })
+        .collect::<Vec<ParseError<Token>>>();
+    for lex_error in lex_errors {
+        let in_string = tokens
+            .iter()
+            .any(|it| it.source.starts_with('"') && contains(it, lex_error));
+        let message = lex_error_message(lex_error.source, in_string);
+        errors.extend(lex_error.clone().error_message::<()>(message).unwrap_err());
+    }
+    (ast, errors)
+}
+
+fn lex_error_message(source: &str, in_string: bool) -> String {
+    if source.starts_with('"') {
+        return "unterminated string".to_string();
+    }
+    if in_string {
+        return format!("invalid escape sequence `{}`", source);
+    }
+    if source.starts_with(char::is_numeric) {
+        return format!("invalid number literal `{}`", source);
+    }
+    format!("unrecognized `{}`", source)
+}
+
+fn contains(outer: &Lexeme<Token>, inner: &Lexeme<Token>) -> bool {
+    let start = (inner.line.0, inner.column.0);
+    (outer.line.0, outer.column.0) <= start && start < (outer.line.1, outer.column.1)
 }
 
-fn parse_use<'l>(walker: Walker<'l, Lexeme<'l, Token>>) -> ParseResult<'l, Token, Ast>{
+enum Declaration {
+    Doc(String),
+    Item(Ast),
+}
+
+fn attach_docs<'l>(
+    declarations: Vec<parser::Chunk<'l, Token, Declaration>>,
+) -> (Vec<Ast>, Vec<ParseError<'l, Token>>) {
+    let documentable = vec![Token::KwDoc, Token::KwTyp, Token::KwDef, Token::KwLet];
+    let mut doc = vec![];
+    let mut ast = vec![];
+    let mut errors = vec![];
+    let mut end = None;
+    for (first, last, declaration) in declarations {
+        end = Some(last.clone());
+        let mut item = match declaration {
+            Ok(Declaration::Doc(text)) => {
+                doc.push(text);
+                continue;
+            }
+            Ok(Declaration::Item(item)) => item,
+            Err(mut error) => {
+                doc.clear();
+                errors.append(&mut error);
+                ast.push(Ast::Error {
+                    line: (first.line.0, last.line.1),
+                    column: (first.column.0, last.column.1),
+                });
+                continue;
+            }
+        };
+        match &mut item {
+            Ast::Typ { doc: item_doc }
+            | Ast::Def { doc: item_doc }
+            | Ast::Let { doc: item_doc } => {
+                *item_doc = std::mem::take(&mut doc);
+            }
+            _ if !doc.is_empty() => {
+                doc.clear();
+                errors.extend(first.error::<()>(documentable.clone()).unwrap_err());
+            }
+            _ => {}
+        }
+        ast.push(item);
+    }
+    if !doc.is_empty() {
+        errors.extend(ParseError::none::<()>(documentable, end.as_ref()).unwrap_err());
+    }
+    (ast, errors)
+}
+
+fn parse_use<'l>(mut walker: Walker<'l, Lexeme<'l, Token>>) -> ParseResult<'l, Token, Ast>{
+    let from = parser::expect(&mut walker, &[Token::String])?;
+    let Some(from_text) = lexer::unescape(from.source) else {
+        return from.error(vec![Token::String]);
+    };
+    let name = match walker.peek() {
+        Some(next) if next.token == Token::ValueName => {
+            Some(parser::expect(&mut walker, &[Token::ValueName])?.source.to_string())
+        }
+        _ => None,
+    };
+    let mut items = vec![];
+    if walker.peek().is_some() {
+        parser::expect(&mut walker, &[Token::SquareOpen])?;
+        loop {
+            let item = parser::expect(
+                &mut walker,
+                &[
+                    Token::ValueName,
+                    Token::TypeName,
+                    Token::Asterisk,
+                    Token::SquareClose,
+                ],
+            )?;
+            let name = match item.token {
+                Token::SquareClose => break,
+                Token::Asterisk => {
+                    items.push(Import::Glob);
+                    continue;
+                }
+                _ => item.source.to_string(),
+            };
+            match walker.peek() {
+                Some(next) if next.token == Token::KwAs => {
+                    parser::expect(&mut walker, &[Token::KwAs])?;
+                    let alias = parser::expect(&mut walker, &[item.token])?;
+                    items.push(Import::Alias {
+                        name,
+                        alias: alias.source.to_string(),
+                    });
+                }
+                _ => items.push(Import::Name(name)),
+            }
+        }
+    }
+    parser::expect_end(&mut walker, &TOP_LEVEL_KEYWORDS)?;
     Ok(Ast::Use {
-        from: "".to_string(),
-        name: None,
-        items: vec![],
+        from: from_text,
+        name,
+        items,
+        line: from.line,
+        column: from.column,
     })
 }
-fn parse_doc<'l>(walker: Walker<'l, Lexeme<'l, Token>>) -> ParseResult<'l, Token, Ast>{
-    Ok(Ast::Doc("".to_string()))
+fn parse_doc<'l>(mut walker: Walker<'l, Lexeme<'l, Token>>) -> ParseResult<'l, Token, String>{
+    let doc = parser::expect(&mut walker, &[Token::String])?;
+    let Some(text) = lexer::unescape(doc.source) else {
+        return doc.error(vec![Token::String]);
+    };
+    parser::expect_end(&mut walker, &TOP_LEVEL_KEYWORDS)?;
+    Ok(text)
 }
-fn parse_typ<'l>(walker: Walker<'l, Lexeme<'l, Token>>) -> ParseResult<'l, Token, Ast>{
-    Ok(Ast::Typ)
+fn parse_typ<'l>(_walker: Walker<'l, Lexeme<'l, Token>>) -> ParseResult<'l, Token, Ast>{
+    Ok(Ast::Typ { doc: vec![] })
 }
-fn parse_def<'l>(walker: Walker<'l, Lexeme<'l, Token>>) -> ParseResult<'l, Token, Ast>{
-    Ok(Ast::Def)
+fn parse_def<'l>(_walker: Walker<'l, Lexeme<'l, Token>>) -> ParseResult<'l, Token, Ast>{
+    Ok(Ast::Def { doc: vec![] })
 }
-fn parse_let<'l>(walker: Walker<'l, Lexeme<'l, Token>>) -> ParseResult<'l, Token, Ast>{
-    Ok(Ast::Let)
+fn parse_let<'l>(_walker: Walker<'l, Lexeme<'l, Token>>) -> ParseResult<'l, Token, Ast>{
+    Ok(Ast::Let { doc: vec![] })
 }
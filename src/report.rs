@@ -0,0 +1,51 @@
+use libparsing::parse_error::Diagnostic;
+use std::io::{self, Write};
+
+pub struct Config {
+    pub max_errors: usize,
+    pub format: ErrorFormat,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            max_errors: 50,
+            format: ErrorFormat::Pretty,
+        }
+    }
+}
+
+pub enum ErrorFormat {
+    Pretty,
+    Short,
+}
+
+#[derive(Debug, Default)]
+pub struct Summary {
+    pub errors: usize,
+}
+
+pub fn show_errors<'e>(
+    errors: impl IntoIterator<Item = &'e Diagnostic>,
+    config: &Config,
+    out: &mut impl Write,
+) -> io::Result<Summary> {
+    let mut summary = Summary::default();
+    for error in errors {
+        if config.max_errors == 0 || summary.errors < config.max_errors {
+            match config.format {
+                ErrorFormat::Pretty => writeln!(out, "{}", error.fancy_print())?,
+                ErrorFormat::Short => writeln!(out, "{}", error.short_print())?,
+            }
+        }
+        summary.errors += 1;
+    }
+    if config.max_errors != 0 && summary.errors > config.max_errors {
+        writeln!(
+            out,
+            "... and {} more errors (rerun with --max-errors=0 to see all)",
+            summary.errors - config.max_errors
+        )?;
+    }
+    Ok(summary)
+}
@@ -0,0 +1,190 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub trait SourceProvider {
+    fn read(&self, path: &str) -> Option<String>;
+    fn exists(&self, path: &str) -> bool {
+        self.read(path).is_some()
+    }
+}
+
+pub struct DiskSource;
+
+impl SourceProvider for DiskSource {
+    fn read(&self, path: &str) -> Option<String> {
+        fs::read_to_string(path).ok()
+    }
+    fn exists(&self, path: &str) -> bool {
+        Path::new(path).is_file()
+    }
+}
+
+pub struct MemorySource {
+    files: HashMap<String, String>,
+}
+
+impl MemorySource {
+    pub fn new(files: HashMap<String, String>) -> Self {
+        Self { files }
+    }
+    pub fn insert(&mut self, path: &str, source: String) {
+        self.files.insert(path.to_string(), source);
+    }
+}
+
+impl SourceProvider for MemorySource {
+    fn read(&self, path: &str) -> Option<String> {
+        self.files.get(path).cloned()
+    }
+    fn exists(&self, path: &str) -> bool {
+        self.files.contains_key(path)
+    }
+}
+
+pub fn expand_inputs(inputs: &[String]) -> Result<Vec<String>, String> {
+    let mut paths = vec![];
+    for input in inputs {
+        let path = Path::new(input);
+        let found = paths.len();
+        if path
+            .parent()
+            .is_some_and(|it| it.to_string_lossy().contains(['*', '?']))
+        {
+            return Err(format!(
+                "error: wildcards are only supported in file names, not in `{}`",
+                input
+            ));
+        }
+        if path.is_dir() {
+            find_sources(path, &mut paths);
+        } else if let Some(pattern) = path.file_name().and_then(|it| it.to_str())
+            && pattern.contains(['*', '?'])
+        {
+            let directory = path.parent().unwrap_or(Path::new(""));
+            let directory = if directory.as_os_str().is_empty() {
+                Path::new(".")
+            } else {
+                directory
+            };
+            let entries = fs::read_dir(directory).into_iter().flatten();
+            let mut matches = entries
+                .filter_map(|entry| entry.ok())
+                .filter(|entry| {
+                    entry
+                        .file_name()
+                        .to_str()
+                        .is_some_and(|name| matches_glob(pattern, name))
+                })
+                .map(|entry| path.with_file_name(entry.file_name()))
+                .collect::<Vec<PathBuf>>();
+            matches.sort();
+            for path in matches {
+                if path.is_dir() {
+                    find_sources(&path, &mut paths);
+                } else if path.extension().is_some_and(|it| it == "soup") {
+                    paths.push(path.display().to_string());
+                }
+            }
+        } else {
+            paths.push(input.clone());
+        }
+        if paths.len() == found {
+            return Err(format!("error: no input files matched `{}`", input));
+        }
+    }
+    Ok(paths)
+}
+
+fn find_sources(directory: &Path, paths: &mut Vec<String>) {
+    let Ok(entries) = fs::read_dir(directory) else {
+        return;
+    };
+    let mut entries = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .collect::<Vec<PathBuf>>();
+    entries.sort();
+    for path in entries {
+        if path.is_dir() {
+            find_sources(&path, paths);
+        } else if path.extension().is_some_and(|it| it == "soup") {
+            paths.push(path.display().to_string());
+        }
+    }
+}
+
+fn matches_glob(pattern: &str, name: &str) -> bool {
+    let pattern = pattern.chars().collect::<Vec<char>>();
+    let name = name.chars().collect::<Vec<char>>();
+    let (mut p, mut n) = (0, 0);
+    let mut star = None;
+    while n < name.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == name[n]) {
+            p += 1;
+            n += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p, n));
+            p += 1;
+        } else if let Some((star_p, star_n)) = star {
+            p = star_p + 1;
+            n = star_n + 1;
+            star = Some((star_p, star_n + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|it| *it == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    fn scratch(name: &str) -> PathBuf {
+        let directory = env::temp_dir().join(format!("soup-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&directory);
+        fs::create_dir_all(&directory).expect("Failed to create scratch directory");
+        directory
+    }
+
+    #[test]
+    fn glob_matches_wildcards() {
+        assert!(matches_glob("*.soup", "main.soup"));
+        assert!(matches_glob("ma?n.soup", "main.soup"));
+        assert!(matches_glob("main*", "main.soup"));
+        assert!(matches_glob("main*", "main"));
+        assert!(matches_glob("a*b*c", "aXbYbc"));
+        assert!(!matches_glob("a*b*c", "aXbYbd"));
+        assert!(!matches_glob("*.soup", "main.txt"));
+        assert!(!matches_glob("ma?n", "man"));
+    }
+
+    #[test]
+    fn expand_inputs_matches_soup_files() {
+        let directory = scratch("expand");
+        fs::create_dir(directory.join("project")).unwrap();
+        for file in ["b.soup", "a.soup", "notes.txt", "project/main.soup"] {
+            fs::write(directory.join(file), "").unwrap();
+        }
+        let pattern = directory.join("*").display().to_string();
+        let expected = ["a.soup", "b.soup", "project/main.soup"]
+            .map(|file| directory.join(file).display().to_string());
+        assert_eq!(expand_inputs(&[pattern]), Ok(expected.to_vec()));
+        fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn expand_inputs_reports_unmatched_inputs() {
+        let directory = scratch("unmatched");
+        let pattern = directory.join("*.soup").display().to_string();
+        assert_eq!(
+            expand_inputs(std::slice::from_ref(&pattern)),
+            Err(format!("error: no input files matched `{}`", pattern))
+        );
+        let nested = directory.join("*").join("main.soup").display().to_string();
+        assert!(expand_inputs(&[nested]).is_err());
+        fs::remove_dir_all(directory).unwrap();
+    }
+}
@@ -9,6 +9,10 @@ pub enum Token {
     Comma,
     Period,
     Hashtag,
+    Asterisk,
+    DoubleAmpersand,
+    DoublePipe,
+    ExclamationMark,
     SquareOpen,
     SquareClose,
     RoundOpen,
@@ -23,6 +27,9 @@ pub enum Token {
     KwPub,
     KwUse,
     KwDoc,
+    KwAs,
+    KwTrue,
+    KwFalse,
     LexError,
 }
 
@@ -36,6 +43,10 @@ impl ParseErrorToken for Token {
             Token::Comma => "`,`",
             Token::Period => "`.`",
             Token::Hashtag => "`#`",
+            Token::Asterisk => "`*`",
+            Token::DoubleAmpersand => "`&&`",
+            Token::DoublePipe => "`||`",
+            Token::ExclamationMark => "`!`",
             Token::SquareOpen => "`[`",
             Token::SquareClose => "`]`",
             Token::RoundOpen => "`(`",
@@ -50,6 +61,9 @@ impl ParseErrorToken for Token {
             Token::KwPub => "`pub`",
             Token::KwUse => "`use`",
             Token::KwDoc => "`doc`",
+            Token::KwAs => "`as`",
+            Token::KwTrue => "`true`",
+            Token::KwFalse => "`false`",
             Token::LexError => "<ERROR>",
         }
     }